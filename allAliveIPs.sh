@@ -2,7 +2,7 @@
 
 # Network subnet
 # subnet="10.0.0"
-subnet="172.20.10"
+subnet="${1:-172.20.10}"  # override with the first argument, e.g. ./allAliveIPs.sh 192.168.1
 
 # Loop through all possible hosts in the subnet
 for i in $(seq 1 254); do
@@ -1,7 +1,7 @@
 #!/bin/bash
 
 # Network subnet
-subnet="172.20.10"  
+subnet="${1:-172.20.10}"  # override with the first argument, e.g. ./allAliveIPsimproved.sh 192.168.1
 
 # Function to ping a single IP
 ping_ip() {
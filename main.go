@@ -2,6 +2,7 @@ package main
 
 import (
 	"fmt"
+	"os"
 	"os/exec"
 	"sync"
 	"time"
@@ -23,6 +24,12 @@ func pingIP(ip string, wg *sync.WaitGroup, results chan<- string) {
 func main() {
 	// subnet := "10.0.0"
 	subnet := "172.20"
+
+	// Subnet can be given as the first argument, e.g. `go run main.go 192.168.1`
+	if len(os.Args) > 1 {
+		subnet = os.Args[1]
+	}
+
 	results := make(chan string, 254) // Buffered channel to hold results
 	var wg sync.WaitGroup
 
@@ -1,9 +1,219 @@
-use std::process::Command;
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use std::io::{self, Write};
 
+// Hosts are probed by spawning the system `ping` binary, which wasm targets can't do
+#[cfg(target_family = "wasm")]
+compile_error!("network scanning is not supported on this target (no process spawning for `ping`)");
+
+/// Status markers printed next to hosts
+#[derive(Clone, Copy)]
+struct Symbols {
+    /// Marks a host that answered the ping
+    alive: &'static str,
+    /// Marks a host whose probe failed, so its state is unknown
+    unknown: &'static str,
+}
+
+impl Symbols {
+    /// Unicode markers, the default
+    const EMOJI: Symbols = Symbols { alive: "✓", unknown: "?" };
+    /// Plain ASCII markers for terminals and logs that can't render Unicode
+    const ASCII: Symbols = Symbols { alive: "[+]", unknown: "[?]" };
+}
+
+/// How often the progress line is redrawn
+#[derive(Clone, Copy)]
+enum ProgressCadence {
+    /// After every `n` finished probes
+    EveryHosts(usize),
+    /// At most once per interval, however many probes finish in it
+    Every(Duration),
+}
+
+/// Command-line options
+struct Options {
+    /// Scan link-local and multicast subnets instead of rejecting them
+    allow_special: bool,
+    /// Trace the route to each alive host after the scan
+    traceroute: bool,
+    /// Subnets passed with --subnets; when empty the user is prompted for one
+    subnets: Vec<String>,
+    /// Abort if ping can't send ICMP or any probe thread panics, instead of warning and carrying on
+    strict: bool,
+    /// Markers used for host status lines, chosen with --theme
+    symbols: Symbols,
+    /// Named Linux network namespace to run probes in, set with --netns
+    netns: Option<String>,
+    /// Maximum sweeps to run while waiting for the alive set to settle, set with --until-stable
+    max_sweeps: usize,
+    /// Probe the usual gateway addresses before the rest of each subnet, set with --gateways-first
+    gateways_first: bool,
+    /// When to redraw the progress line, set with --progress-every or --progress-interval
+    progress_cadence: ProgressCadence,
+    /// Hex-encoded marker carried in every echo request's payload, set with --signature
+    signature: Option<String>,
+    /// List alive hosts under a header per /24 subnet, set with --group-by subnet
+    group_by_subnet: bool,
+    /// Shell command template to run against each alive host, set with --exec
+    exec: Option<String>,
+    /// Show a coarse OS guess from each reply's TTL, set with --os-guess
+    os_guess: bool,
+    /// Number of fastest-replying hosts to list after the scan, set with --top-responders
+    top_responders: Option<usize>,
+}
+
+/// Prints the command-line usage, including the --exec shell warning
+fn print_usage() {
+    eprintln!("Usage: network_scanner [--subnets LIST] [--allow-special] [--traceroute] [--strict] [--theme emoji|ascii] [--netns NAME] [--until-stable MAX] [--gateways-first] [--progress-every N | --progress-interval MS] [--signature TEXT] [--group-by subnet] [--exec COMMAND] [--os-guess] [--top-responders N]");
+    eprintln!("--gateways-first starts .1 and .254 first so they report early; every host is still probed and the scan takes as long.");
+    eprintln!("Link-local (169.254.x) and multicast (224.x-239.x) subnets are refused unless --allow-special is given.");
+    eprintln!("Warning: --exec runs COMMAND through the system shell for every alive host ({{ip}} is substituted); it can run anything you can.");
+    eprintln!("--exec has no timeout and waits for every command to exit; bound commands that can hang, e.g. --exec \"timeout 10 ssh {{ip}} uptime\".");
+}
+
+/// Parses command-line flags, exiting with a usage message on bad input
+fn parse_args() -> Options {
+    let mut options = Options {
+        allow_special: false,
+        traceroute: false,
+        subnets: Vec::new(),
+        strict: false,
+        symbols: Symbols::EMOJI,
+        netns: None,
+        max_sweeps: 1,
+        gateways_first: false,
+        progress_cadence: ProgressCadence::EveryHosts(1),
+        signature: None,
+        group_by_subnet: false,
+        exec: None,
+        os_guess: false,
+        top_responders: None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--allow-special" => options.allow_special = true,
+            "--traceroute" => options.traceroute = true,
+            "--strict" => options.strict = true,
+            "--gateways-first" => options.gateways_first = true,
+            "--os-guess" => options.os_guess = true,
+            "--netns" => {
+                options.netns = args.next();
+                if options.netns.is_none() {
+                    eprintln!("--netns expects a namespace name");
+                    process::exit(2);
+                }
+            }
+            "--until-stable" => {
+                options.max_sweeps = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => n,
+                    _ => {
+                        eprintln!("--until-stable expects a maximum number of sweeps, e.g. 5");
+                        process::exit(2);
+                    }
+                };
+            }
+            "--top-responders" => {
+                options.top_responders = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => Some(n),
+                    _ => {
+                        eprintln!("--top-responders expects a number of hosts, e.g. 10");
+                        process::exit(2);
+                    }
+                };
+            }
+            "--progress-every" => {
+                options.progress_cadence = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => ProgressCadence::EveryHosts(n),
+                    _ => {
+                        eprintln!("--progress-every expects a number of hosts, e.g. 25");
+                        process::exit(2);
+                    }
+                };
+            }
+            "--progress-interval" => {
+                options.progress_cadence = match args.next().and_then(|ms| ms.parse().ok()) {
+                    Some(ms) => ProgressCadence::Every(Duration::from_millis(ms)),
+                    None => {
+                        eprintln!("--progress-interval expects milliseconds, e.g. 250");
+                        process::exit(2);
+                    }
+                };
+            }
+            "--signature" => {
+                let marker = args.next().unwrap_or_default();
+                // ping -p takes at most 16 pad bytes, written as hex
+                if marker.is_empty() || marker.len() > 16 {
+                    eprintln!("--signature expects a marker of 1 to 16 bytes, e.g. acme-scan");
+                    process::exit(2);
+                }
+                if cfg!(target_os = "windows") {
+                    eprintln!("--signature is not supported: Windows ping can't set the payload");
+                    process::exit(2);
+                }
+                options.signature = Some(marker.bytes().map(|b| format!("{:02x}", b)).collect());
+            }
+            "--group-by" => {
+                if args.next().as_deref() != Some("subnet") {
+                    eprintln!("--group-by expects 'subnet'");
+                    process::exit(2);
+                }
+                options.group_by_subnet = true;
+            }
+            "--exec" => {
+                options.exec = args.next();
+                if options.exec.is_none() {
+                    eprintln!("--exec expects a command template, e.g. \"ssh {{ip}} uptime\"");
+                    process::exit(2);
+                }
+            }
+            "--theme" => {
+                options.symbols = match args.next().as_deref() {
+                    Some("emoji") => Symbols::EMOJI,
+                    Some("ascii") => Symbols::ASCII,
+                    _ => {
+                        eprintln!("--theme expects 'emoji' or 'ascii'");
+                        process::exit(2);
+                    }
+                };
+            }
+            "--subnets" => {
+                let list = args.next().unwrap_or_default();
+                options.subnets = list
+                    .split(',')
+                    .map(|subnet| subnet.trim().to_string())
+                    .filter(|subnet| !subnet.is_empty())
+                    .collect();
+
+                if options.subnets.is_empty() {
+                    eprintln!("--subnets expects a comma-separated list, e.g. 10.0.0,10.0.1");
+                    process::exit(2);
+                }
+            }
+            "--help" | "-h" => {
+                print_usage();
+                process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown option: {}", other);
+                print_usage();
+                process::exit(2);
+            }
+        }
+    }
+
+    options
+}
+
 /// Gets user input from stdin
 fn get_input(prompt: &str) -> String {
     print!("{}", prompt);
@@ -14,42 +224,269 @@ fn get_input(prompt: &str) -> String {
     input.trim().to_string()
 }
 
-/// Validates IP subnet format
-fn is_valid_subnet(subnet: &str) -> bool {
-    let parts: Vec<&str> = subnet.split('.').collect();
-    if parts.len() != 3 {
-        return false;
+/// A /24 subnet given by its first three octets, e.g. 192.168.1
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Subnet([u8; 3]);
+
+impl Subnet {
+    /// Parses a subnet like "192.168.1", normalizing octets so "10.0.01" becomes 10.0.1
+    fn parse(subnet: &str) -> Option<Subnet> {
+        let octets = subnet
+            .split('.')
+            .map(|part| part.parse::<u8>().ok())
+            .collect::<Option<Vec<u8>>>()?;
+
+        match octets.as_slice() {
+            [a, b, c] => Some(Subnet([*a, *b, *c])),
+            _ => None,
+        }
     }
 
-    parts.iter().all(|part| {
-        if let Ok(num) = part.parse::<u8>() {
-            num <= 255
-        } else {
-            false
+    /// Address of the given host within the subnet
+    fn host(self, host: u8) -> Ipv4Addr {
+        let [a, b, c] = self.0;
+        Ipv4Addr::new(a, b, c, host)
+    }
+}
+
+impl fmt::Display for Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b, c] = self.0;
+        write!(f, "{}.{}.{}", a, b, c)
+    }
+}
+
+/// Parses a subnet and checks that it can be scanned, returning the reason if it can't
+fn check_subnet(subnet: &str, allow_special: bool) -> Result<Subnet, String> {
+    let subnet = match Subnet::parse(subnet) {
+        Some(subnet) => subnet,
+        None => return Err(format!("Invalid subnet format '{}'. Please use format like '192.168.1'", subnet)),
+    };
+
+    match special_range(subnet) {
+        Some(kind) if !allow_special => {
+            Err(format!("{} is a {} range. Re-run with --allow-special to scan it.", subnet, kind))
         }
-    })
+        Some(kind) => {
+            println!("Warning: {} is a {} range; results may not reflect individual hosts.", subnet, kind);
+            Ok(subnet)
+        }
+        None => Ok(subnet),
+    }
 }
 
-/// Attempts to ping the given IP address and returns it if successful
-fn ping_ip(ip: String) -> Option<String> {
-    println!("Pinging: {}", ip);
+/// Checks a --subnets list, dropping repeats so each subnet is scanned once
+///
+/// Repeats are compared after normalization ("10.0.1" and "10.0.01" are the
+/// same subnet) and the first occurrence keeps its place in the order.
+fn check_subnets(list: &[String], allow_special: bool) -> Result<Vec<Subnet>, String> {
+    let mut subnets = Vec::new();
+    for subnet in list {
+        let subnet = check_subnet(subnet, allow_special)?;
+        if !subnets.contains(&subnet) {
+            subnets.push(subnet);
+        }
+    }
+    Ok(subnets)
+}
+
+/// Returns the kind of special-purpose range the subnet falls in, if any.
+///
+/// Flagged ranges:
+/// - 169.254.0.0/16: link-local (RFC 3927), only reachable from the local link
+/// - 224.0.0.0/4: multicast (RFC 5771), replies come from group members rather than the address itself
+fn special_range(subnet: Subnet) -> Option<&'static str> {
+    match subnet.0 {
+        [169, 254, _] => Some("link-local"),
+        [224..=239, _, _] => Some("multicast"),
+        _ => None,
+    }
+}
+
+/// Checks that a named network namespace exists and can be entered
+///
+/// Runs `ip netns exec NAME true`, the same way probes enter the namespace,
+/// so a missing namespace or missing privileges fails here instead of
+/// making every host look dead.
+#[cfg(target_os = "linux")]
+fn check_netns(name: &str) -> Result<(), String> {
+    // `ip netns add` creates a bind mount under /run/netns for each named namespace
+    if !std::path::Path::new("/run/netns").join(name).exists() {
+        return Err(format!("Network namespace '{}' not found (see `ip netns list`)", name));
+    }
+
+    let output = probe_command("true", Some(name))
+        .output()
+        .map_err(|e| format!("Could not run `ip netns exec`: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let reason = String::from_utf8_lossy(&output.stderr);
+        Err(format!(
+            "Could not enter network namespace '{}' ({}); `ip netns exec` usually needs root",
+            name,
+            reason.trim()
+        ))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn check_netns(_name: &str) -> Result<(), String> {
+    Err("--netns is only supported on Linux".to_string())
+}
+
+/// Builds the command for a probe tool, entering the network namespace if one is given
+///
+/// Uses `ip netns exec`, so probes originate from the namespace's interfaces.
+/// This needs the same privileges as `ip netns exec` itself (usually root).
+fn probe_command(program: &str, netns: Option<&str>) -> Command {
+    match netns {
+        Some(name) => {
+            let mut command = Command::new("ip");
+            command.args(["netns", "exec", name, program]);
+            command
+        }
+        None => Command::new(program),
+    }
+}
 
+/// Builds a single-echo ping command for the given IP with a one second timeout
+///
+/// A signature (hex) is repeated through the echo payload with `ping -p`, so
+/// the scan can be attributed from packet captures on the target's network.
+fn ping_command(ip: &str, netns: Option<&str>, signature: Option<&str>) -> Command {
     #[cfg(target_os = "windows")]
-    let args = vec!["-n", "1", "-w", "1000", &ip];
+    let args = vec!["-n", "1", "-w", "1000", ip];
     
     #[cfg(not(target_os = "windows"))]
-    let args = vec!["-c", "1", "-W", "1", &ip];
+    let args = vec!["-c", "1", "-W", "1", ip];
+
+    let mut command = probe_command("ping", netns);
+    if let Some(pattern) = signature {
+        command.args(["-p", pattern]);
+    }
+    command.args(&args);
+    command
+}
+
+/// Checks that ping can send ICMP at all by pinging the loopback address
+///
+/// Without this, a ping that lacks privileges makes every host look dead.
+/// It always runs in the host namespace: a namespace made with `ip netns add`
+/// starts with `lo` down, so loopback there says nothing about privileges.
+/// Entering the namespace is checked separately by `check_netns`.
+/// Windows needs no extra privileges and macOS ships ping setuid. On Linux,
+/// ping needs to be setuid or have CAP_NET_RAW, or the user's group must be
+/// within `net.ipv4.ping_group_range` for unprivileged ICMP sockets.
+fn check_ping(signature: Option<&str>) -> Result<(), String> {
+    let output = ping_command("127.0.0.1", None, signature)
+        .output()
+        .map_err(|e| format!("Could not run ping: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let reason = String::from_utf8_lossy(&output.stderr);
+        Err(format!(
+            "ping could not reach 127.0.0.1 ({}); it may lack privileges to send ICMP, so every host would appear dead",
+            reason.trim()
+        ))
+    }
+}
+
+/// Coarse OS family guessed from the TTL of a ping reply
+#[derive(Clone, Copy, Debug)]
+enum OsFamily {
+    /// Linux, macOS and other Unix-likes start at 64
+    Unix,
+    /// Windows starts at 128
+    Windows,
+    /// Routers, switches and other network gear usually start at 255
+    NetworkDevice,
+}
+
+impl OsFamily {
+    /// Guesses the OS from a reply TTL by rounding up to the nearest common initial TTL
+    ///
+    /// This is a heuristic, not a fingerprint: each router hop lowers the TTL,
+    /// and hosts or middleboxes can be configured to send any initial value.
+    fn from_ttl(ttl: u8) -> OsFamily {
+        match ttl {
+            0..=64 => OsFamily::Unix,
+            65..=128 => OsFamily::Windows,
+            _ => OsFamily::NetworkDevice,
+        }
+    }
+}
+
+impl fmt::Display for OsFamily {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            OsFamily::Unix => "Unix-like",
+            OsFamily::Windows => "Windows",
+            OsFamily::NetworkDevice => "network device",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Extracts the reply TTL from ping output ("ttl=64" on Unix, "TTL=128" on Windows)
+fn parse_ttl(output: &str) -> Option<u8> {
+    let lower = output.to_lowercase();
+    let start = lower.find("ttl=")? + "ttl=".len();
+    let digits: String = lower[start..].chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Extracts the round-trip time from ping output ("time=0.42 ms" on Unix, "time=3ms" or "time<1ms" on Windows)
+fn parse_rtt(output: &str) -> Option<Duration> {
+    let lower = output.to_lowercase();
+    let start = lower.find("time=").or_else(|| lower.find("time<"))? + "time=".len();
+    let number: String = lower[start..].chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+    let ms: f64 = number.parse().ok()?;
+    Some(Duration::from_micros((ms * 1000.0) as u64))
+}
+
+/// Parses one hop line of traceroute or tracert output, skipping headers, trailers and blank lines
+///
+/// Hop lines start with the TTL, e.g. " 3  10.0.0.1  4.211 ms" or "  3    4 ms  <1 ms  2 ms  10.0.0.1".
+/// Only the first RTT on the line is kept.
+fn parse_hop(line: &str) -> Option<TraceHop> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let ttl = tokens.first()?.parse::<u8>().ok()?;
+    let addr = tokens.iter().filter_map(|token| token.parse::<Ipv4Addr>().ok()).next();
+    let rtt = tokens
+        .windows(2)
+        .filter(|pair| pair[1] == "ms")
+        .filter_map(|pair| pair[0].trim_start_matches('<').parse::<f64>().ok())
+        .next()
+        .map(|ms| Duration::from_micros((ms * 1000.0) as u64));
+
+    Some(TraceHop { ttl, addr, rtt })
+}
+
+/// What a successful ping reported about a host
+#[derive(Clone, Copy)]
+struct Reply {
+    ip: Ipv4Addr,
+    ttl: Option<u8>,
+    rtt: Option<Duration>,
+}
+
+/// Attempts to ping the given IP address and returns its reply if successful
+fn ping_ip(ip: Ipv4Addr, symbols: Symbols, netns: Option<&str>, signature: Option<&str>) -> Option<Reply> {
+    println!("Pinging: {}", ip);
 
-    let output = Command::new("ping")
-        .args(&args)
-        .output();
+    let output = ping_command(&ip.to_string(), netns, signature).output();
 
     match output {
         Ok(result) => {
             let success = result.status.success();
             if success {
-                println!("✓ {} is alive", ip);
-                Some(ip)
+                println!("{} {} is alive", symbols.alive, ip);
+                let stdout = String::from_utf8_lossy(&result.stdout);
+                Some(Reply { ip, ttl: parse_ttl(&stdout), rtt: parse_rtt(&stdout) })
             } else {
                 None
             }
@@ -61,71 +498,705 @@ fn ping_ip(ip: String) -> Option<String> {
     }
 }
 
-fn main() {
-    println!("=== Network Scanner ===");
-    
-    // Get subnet from user
-    let mut subnet = String::new();
-    loop {
-        subnet = get_input("Enter subnet to scan (e.g., 192.168.1): ");
-        
-        if is_valid_subnet(&subnet) {
-            break;
+/// A single hop on the route to a host
+struct TraceHop {
+    ttl: u8,
+    addr: Option<Ipv4Addr>,
+    rtt: Option<Duration>,
+}
+
+/// Most hops --traceroute follows before giving up on a host
+const MAX_TRACE_HOPS: u8 = 30;
+
+/// Traces the route to the given IP using the system traceroute tool
+///
+/// Like `ping_ip`, this shells out instead of opening raw sockets, so it needs
+/// no extra privileges. Hops that didn't answer have no address or RTT.
+fn traceroute(ip: Ipv4Addr, max_hops: u8, netns: Option<&str>) -> Vec<TraceHop> {
+    let ip = ip.to_string();
+    let max_hops = max_hops.to_string();
+
+    #[cfg(target_os = "windows")]
+    let (program, args) = ("tracert", vec!["-d", "-h", &max_hops, "-w", "1000", &ip]);
+
+    #[cfg(not(target_os = "windows"))]
+    let (program, args) = ("traceroute", vec!["-n", "-q", "1", "-w", "1", "-m", &max_hops, &ip]);
+
+    let output = match probe_command(program, netns).args(&args).output() {
+        Ok(result) => result,
+        Err(e) => {
+            println!("Error tracing route to {}: {}", ip, e);
+            return Vec::new();
         }
-        println!("Invalid subnet format. Please use format like '192.168.1'");
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_hop)
+        .collect()
+}
+
+/// Common gateway host octets, probed first with --gateways-first
+const GATEWAY_HOSTS: [u8; 2] = [1, 254];
+
+/// Returns the host octets to probe in order, optionally with likely gateways at the front
+///
+/// This only changes the order probes start and report live in. Every probe
+/// is still joined before results are printed, so the sweep takes as long.
+fn host_order(gateways_first: bool) -> Vec<u8> {
+    let mut hosts: Vec<u8> = (1..=254).collect();
+    if gateways_first {
+        hosts.retain(|host| !GATEWAY_HOSTS.contains(host));
+        hosts.splice(0..0, GATEWAY_HOSTS.iter().cloned());
     }
+    hosts
+}
 
+/// Outcome of sweeping a single subnet
+struct SubnetScan {
+    /// IPs that answered the ping
+    alive: Vec<Ipv4Addr>,
+    /// IPs whose probe thread panicked, so their state is unknown
+    failed: Vec<Ipv4Addr>,
+    /// Reply TTL of each alive IP, where ping reported one
+    ttls: BTreeMap<Ipv4Addr, u8>,
+    /// Round-trip time of each alive IP, where ping reported one
+    rtts: BTreeMap<Ipv4Addr, Duration>,
+}
+
+/// Probes finished so far in one subnet sweep
+struct Progress {
+    completed: usize,
+    last_drawn: Instant,
+}
+
+/// A running probe thread for one IP
+struct Probe {
+    ip: Ipv4Addr,
+    handle: JoinHandle<()>,
+    /// Set once the probe has counted itself towards progress
+    counted: Arc<AtomicBool>,
+}
+
+/// Counts one finished probe and redraws the progress line when the cadence calls for it
+///
+/// `counted` is set together with the increment, before drawing, so a probe
+/// that panics while printing isn't counted a second time when joined.
+/// The line is always drawn for the final probe so the sweep ends on 100%.
+fn report_progress(progress: &Mutex<Progress>, counted: &AtomicBool, total_ips: usize, cadence: ProgressCadence) {
+    // A panicked probe may have poisoned the lock; the count itself is still valid
+    let mut progress = progress.lock().unwrap_or_else(PoisonError::into_inner);
+    progress.completed += 1;
+    counted.store(true, Ordering::SeqCst);
+
+    let due = match cadence {
+        ProgressCadence::EveryHosts(n) => progress.completed.is_multiple_of(n),
+        ProgressCadence::Every(interval) => progress.last_drawn.elapsed() >= interval,
+    };
+    if !due && progress.completed < total_ips {
+        return;
+    }
+
+    progress.last_drawn = Instant::now();
+    print!("\rProgress: {}/{}  ({:.1}%)", progress.completed, total_ips, (progress.completed as f32 / total_ips as f32) * 100.0);
+    io::stdout().flush().unwrap();
+}
+
+/// Waits for every probe thread and returns the IPs whose probe panicked
+///
+/// Panicked probes that hadn't yet counted themselves are counted here, so
+/// progress always reaches the total. In strict mode the first panicked probe
+/// fails the scan with its IP instead.
+fn join_probes(
+    probes: Vec<Probe>,
+    strict: bool,
+    progress: &Mutex<Progress>,
+    total_ips: usize,
+    cadence: ProgressCadence,
+) -> Result<Vec<Ipv4Addr>, String> {
+    let mut failed = Vec::new();
+    for probe in probes {
+        if probe.handle.join().is_err() {
+            if strict {
+                return Err(format!("Probe for {} panicked; aborting scan (--strict)", probe.ip));
+            }
+
+            println!("\nWarning: probe for {} panicked; its result was lost", probe.ip);
+            if !probe.counted.load(Ordering::SeqCst) {
+                report_progress(progress, &probe.counted, total_ips, cadence);
+            }
+            failed.push(probe.ip);
+        }
+    }
+
+    Ok(failed)
+}
+
+/// Pings every host in the subnet's .1-254 range and collects the alive IPs
+///
+/// A probe thread that panics loses its host's result. In strict mode that
+/// fails the whole scan with the offending IP; otherwise the IP is recorded
+/// as failed and still counted towards progress.
+fn scan_subnet(subnet: Subnet, options: &Options) -> Result<SubnetScan, String> {
     println!("\nStarting scan of subnet: {}.1-254", subnet);
     println!("This may take a few minutes...\n");
 
     let results = Arc::new(Mutex::new(Vec::new()));
-    let mut handles = vec![];
+    let mut probes = vec![];
 
     // Show a simple progress indicator
     let total_ips = 254;
-    let progress = Arc::new(Mutex::new(0));
+    let progress = Arc::new(Mutex::new(Progress { completed: 0, last_drawn: Instant::now() }));
+    let cadence = options.progress_cadence;
 
     // Spawn threads for each IP in the range
-    for i in 1..=254 {
-        let ip = format!("{}.{}", subnet, i);
+    for i in host_order(options.gateways_first) {
+        let ip = subnet.host(i);
         let results = Arc::clone(&results);
         let progress = Arc::clone(&progress);
+        let counted = Arc::new(AtomicBool::new(false));
+        let probe_counted = Arc::clone(&counted);
+        let symbols = options.symbols;
+        let netns = options.netns.clone();
+        let signature = options.signature.clone();
 
         let handle = thread::spawn(move || {
-            if let Some(alive_ip) = ping_ip(ip) {
-                let mut results = results.lock().unwrap();
-                results.push(alive_ip);
+            if let Some(reply) = ping_ip(ip, symbols, netns.as_deref(), signature.as_deref()) {
+                let mut results = results.lock().unwrap_or_else(PoisonError::into_inner);
+                results.push(reply);
             }
             
             // Update and show progress
-            let mut progress = progress.lock().unwrap();
-            *progress += 1;
-            print!("\rProgress: {}/{}  ({:.1}%)", *progress, total_ips, (*progress as f32 / total_ips as f32) * 100.0);
-            io::stdout().flush().unwrap();
+            report_progress(&progress, &probe_counted, total_ips, cadence);
         });
 
-        handles.push(handle);
+        probes.push(Probe { ip, handle, counted });
         
         // Add a small delay between spawns to prevent overwhelming the system
         thread::sleep(Duration::from_millis(10));
     }
 
     // Wait for all threads to complete
+    let failed = join_probes(probes, options.strict, &progress, total_ips, cadence)?;
+
+    let replies = results.lock().unwrap_or_else(PoisonError::into_inner).clone();
+    let ttls = replies.iter().filter_map(|reply| reply.ttl.map(|ttl| (reply.ip, ttl))).collect();
+    let rtts = replies.iter().filter_map(|reply| reply.rtt.map(|rtt| (reply.ip, rtt))).collect();
+    let alive = replies.iter().map(|reply| reply.ip).collect();
+    Ok(SubnetScan { alive, failed, ttls, rtts })
+}
+
+/// Scans subnets one after another and merges their results, exiting on a strict-mode failure
+fn scan_subnets(subnets: &[Subnet], options: &Options) -> SubnetScan {
+    let mut merged = SubnetScan { alive: Vec::new(), failed: Vec::new(), ttls: BTreeMap::new(), rtts: BTreeMap::new() };
+
+    for &subnet in subnets {
+        match scan_subnet(subnet, options) {
+            Ok(scan) => {
+                merged.alive.extend(scan.alive);
+                merged.failed.extend(scan.failed);
+                merged.ttls.extend(scan.ttls);
+                merged.rtts.extend(scan.rtts);
+            }
+            Err(message) => {
+                eprintln!("\n{}", message);
+                process::exit(1);
+            }
+        }
+    }
+
+    merged
+}
+
+/// Returns up to `count` hosts with the lowest round-trip time, fastest first
+fn fastest_hosts(rtts: &BTreeMap<Ipv4Addr, Duration>, count: usize) -> Vec<(Ipv4Addr, Duration)> {
+    let mut hosts: Vec<(Ipv4Addr, Duration)> = rtts.iter().map(|(&ip, &rtt)| (ip, rtt)).collect();
+    hosts.sort_by_key(|&(ip, rtt)| (rtt, ip));
+    hosts.truncate(count);
+    hosts
+}
+
+/// Groups alive IPs by their /24 network address, with each group sorted by IP
+fn group_by_subnet(results: &[Ipv4Addr]) -> BTreeMap<Ipv4Addr, Vec<Ipv4Addr>> {
+    let mut groups: BTreeMap<Ipv4Addr, Vec<Ipv4Addr>> = BTreeMap::new();
+
+    for &ip in results {
+        let [a, b, c, _] = ip.octets();
+        groups.entry(Ipv4Addr::new(a, b, c, 0)).or_default().push(ip);
+    }
+    for hosts in groups.values_mut() {
+        hosts.sort();
+    }
+
+    groups
+}
+
+/// Computes a stable fingerprint of the set of alive IPs
+///
+/// Two sweeps that found the same hosts give the same value regardless of the
+/// order probes finished in, so runs can be compared with a single number
+/// before diffing the lists. Every address is hashed; there is no parsing
+/// step that could skip hosts. Only the addresses go in: the RTTs and TTLs a
+/// scan also records are left out, so a host answering faster or through
+/// another hop doesn't change the value. Uses FNV-1a so the value doesn't
+/// change between builds or Rust versions.
+fn alive_fingerprint(results: &[Ipv4Addr]) -> u64 {
+    let alive: BTreeSet<Ipv4Addr> = results.iter().cloned().collect();
+
+    alive.iter().flat_map(|ip| ip.octets()).fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Repeats a sweep until two consecutive runs find the same alive hosts or `max_sweeps` is hit
+///
+/// Useful on lossy networks where a single sweep misses hosts at random.
+/// `sweep` is called with the 1-based sweep number. Returns the results of the last sweep.
+fn scan_until_stable(max_sweeps: usize, mut sweep: impl FnMut(usize) -> SubnetScan) -> SubnetScan {
+    let mut run = |n: usize| {
+        println!("\n\n=== Sweep {}/{} ===", n, max_sweeps);
+        sweep(n)
+    };
+
+    let mut scan = run(1);
+    for n in 2..=max_sweeps {
+        let previous: BTreeSet<Ipv4Addr> = scan.alive.iter().cloned().collect();
+        scan = run(n);
+        let current: BTreeSet<Ipv4Addr> = scan.alive.iter().cloned().collect();
+
+        if previous == current {
+            println!("\nAlive hosts unchanged since the previous sweep; stopping after {} sweeps", n);
+            return scan;
+        }
+    }
+
+    if max_sweeps > 1 {
+        println!("\nAlive hosts still changing after {} sweeps; using the last sweep", max_sweeps);
+    }
+    scan
+}
+
+/// Most --exec commands allowed to run at once
+const EXEC_CONCURRENCY: usize = 8;
+
+/// Builds the shell command for one host by replacing every `{ip}` in the template with its address
+fn expand_template(template: &str, ip: Ipv4Addr) -> String {
+    template.replace("{ip}", &ip.to_string())
+}
+
+/// Runs a command template against each alive host, at most EXEC_CONCURRENCY at a time
+///
+/// `{ip}` in the template is replaced with the host's address and the result
+/// is handed to the system shell as-is, so it can run anything the user can.
+/// Each host's output is captured and printed as one block once its command exits.
+/// With --netns the shell runs inside the namespace, like the probes did.
+/// There is no timeout: a command that never exits holds its worker, and this
+/// waits for it, so templates that can hang should bound themselves.
+fn exec_on_hosts(template: &str, hosts: &[Ipv4Addr], netns: Option<&str>) {
+    // Reversed so popping from the end hands hosts out in report order
+    let queue = Arc::new(Mutex::new(hosts.iter().rev().cloned().collect::<Vec<_>>()));
+    let mut handles = vec![];
+
+    for _ in 0..EXEC_CONCURRENCY.min(hosts.len()) {
+        let queue = Arc::clone(&queue);
+        let template = template.to_string();
+        let netns = netns.map(String::from);
+
+        let handle = thread::spawn(move || loop {
+            let ip = match queue.lock().unwrap_or_else(PoisonError::into_inner).pop() {
+                Some(ip) => ip,
+                None => break,
+            };
+            let command = expand_template(&template, ip);
+
+            #[cfg(target_os = "windows")]
+            let output = probe_command("cmd", netns.as_deref()).args(["/C", &command]).output();
+
+            #[cfg(not(target_os = "windows"))]
+            let output = probe_command("sh", netns.as_deref()).args(["-c", &command]).output();
+
+            // Hold stdout for the whole block so concurrent hosts don't interleave
+            let stdout = io::stdout();
+            let mut out = stdout.lock();
+            match output {
+                Ok(result) => {
+                    let _ = writeln!(out, "\n--- {} ({}) ---", ip, result.status);
+                    let _ = out.write_all(&result.stdout);
+                    let _ = out.write_all(&result.stderr);
+                }
+                Err(e) => {
+                    let _ = writeln!(out, "\n--- {} ---\nError running command: {}", ip, e);
+                }
+            }
+        });
+
+        handles.push(handle);
+    }
+
     for handle in handles {
-        handle.join().unwrap();
+        let _ = handle.join();
     }
+}
+
+fn main() {
+    println!("=== Network Scanner ===");
+
+    let options = parse_args();
+
+    if let Some(name) = &options.netns {
+        if let Err(message) = check_netns(name) {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+
+    // A broken ping would report every host dead, so fail loudly in strict mode
+    if let Err(message) = check_ping(options.signature.as_deref()) {
+        if options.strict {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+        println!("Warning: {}", message);
+    }
+
+    // Subnets from --subnets must all be valid up front; otherwise prompt for one
+    let subnets = if options.subnets.is_empty() {
+        loop {
+            let subnet = get_input("Enter subnet to scan (e.g., 192.168.1): ");
+
+            match check_subnet(&subnet, options.allow_special) {
+                Ok(subnet) => break vec![subnet],
+                Err(message) => println!("{}", message),
+            }
+        }
+    } else {
+        check_subnets(&options.subnets, options.allow_special).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        })
+    };
+
+    let scan = if options.max_sweeps > 1 {
+        scan_until_stable(options.max_sweeps, |_| scan_subnets(&subnets, &options))
+    } else {
+        scan_subnets(&subnets, &options)
+    };
+    let results = scan.alive;
+    let failed = scan.failed;
+    let ttls = scan.ttls;
+    let rtts = scan.rtts;
+
+    // Appended to each alive host line when --os-guess is set
+    let describe = |ip: &Ipv4Addr| match ttls.get(ip) {
+        Some(&ttl) if options.os_guess => format!(" (ttl {}, likely {})", ttl, OsFamily::from_ttl(ttl)),
+        _ => String::new(),
+    };
 
     // Print final results
     println!("\n\nScan completed! Results:");
     println!("------------------------");
     
-    let results = results.lock().unwrap();
     if results.is_empty() {
-        println!("No responsive IPs found in subnet {}", subnet);
+        let subnets: Vec<String> = subnets.iter().map(Subnet::to_string).collect();
+        println!("No responsive IPs found in subnet {}", subnets.join(", "));
     } else {
-        println!("Found {} active IPs:", results.len());
-        for ip in results.iter() {
-            println!("✓ {}", ip);
+        println!("Found {} active IPs (fingerprint {:016x}):", results.len(), alive_fingerprint(&results));
+        if options.group_by_subnet {
+            for (network, hosts) in group_by_subnet(&results) {
+                println!("\n{}/24 ({} alive)", network, hosts.len());
+                for ip in hosts {
+                    println!("{} {}{}", options.symbols.alive, ip, describe(&ip));
+                }
+            }
+        } else {
+            for ip in results.iter() {
+                println!("{} {}{}", options.symbols.alive, ip, describe(ip));
+            }
+        }
+
+        // Alive hosts are listed in completion order above; this ranks the quickest
+        if let Some(count) = options.top_responders {
+            println!("\nTop responders:");
+            for (rank, (ip, rtt)) in fastest_hosts(&rtts, count).into_iter().enumerate() {
+                println!("{:>3}. {}  {:.2} ms", rank + 1, ip, rtt.as_secs_f64() * 1000.0);
+            }
+        }
+
+        if options.traceroute {
+            for &ip in results.iter() {
+                println!("\nRoute to {}:", ip);
+                for hop in traceroute(ip, MAX_TRACE_HOPS, options.netns.as_deref()) {
+                    match (hop.addr, hop.rtt) {
+                        (Some(addr), Some(rtt)) => println!("{:>3}  {}  {:.1} ms", hop.ttl, addr, rtt.as_secs_f64() * 1000.0),
+                        (Some(addr), None) => println!("{:>3}  {}", hop.ttl, addr),
+                        _ => println!("{:>3}  *", hop.ttl),
+                    }
+                }
+            }
+        }
+
+        if let Some(template) = &options.exec {
+            println!("\nRunning `{}` on {} hosts:", template, results.len());
+            exec_on_hosts(template, &results, options.netns.as_deref());
+        }
+    }
+
+    if !failed.is_empty() {
+        println!("\n{} probes failed, state unknown:", failed.len());
+        for ip in failed.iter() {
+            println!("{} {}", options.symbols.unknown, ip);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_progress() -> Mutex<Progress> {
+        Mutex::new(Progress { completed: 0, last_drawn: Instant::now() })
+    }
+
+    fn probe(ip: &str, panics: bool) -> Probe {
+        let handle = thread::spawn(move || {
+            if panics {
+                panic!("injected probe failure");
+            }
+        });
+        Probe { ip: ip.parse().unwrap(), handle, counted: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Spawns a probe that counts itself like a real one, optionally panicking before or after
+    fn counting_probe(ip: &str, progress: &Arc<Mutex<Progress>>, panic_before: bool, panic_after: bool) -> Probe {
+        let counted = Arc::new(AtomicBool::new(false));
+        let probe_counted = Arc::clone(&counted);
+        let progress = Arc::clone(progress);
+
+        let handle = thread::spawn(move || {
+            if panic_before {
+                panic!("injected probe failure");
+            }
+            report_progress(&progress, &probe_counted, 3, ProgressCadence::EveryHosts(1));
+            if panic_after {
+                panic!("injected output failure");
+            }
+        });
+        Probe { ip: ip.parse().unwrap(), handle, counted }
+    }
+
+    /// A sweep result with the given hosts of 10.0.0 alive
+    fn sweep_of(hosts: &[u8]) -> SubnetScan {
+        SubnetScan {
+            alive: hosts.iter().map(|&host| Ipv4Addr::new(10, 0, 0, host)).collect(),
+            failed: Vec::new(),
+            ttls: BTreeMap::new(),
+            rtts: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn scan_until_stable_stops_once_two_sweeps_match() {
+        let mut sweeps = 0;
+        let scan = scan_until_stable(5, |_| {
+            sweeps += 1;
+            sweep_of(&[1, 7])
+        });
+
+        assert_eq!(sweeps, 2);
+        assert_eq!(scan.alive, vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 7)]);
+    }
+
+    #[test]
+    fn scan_until_stable_returns_last_sweep_when_results_keep_changing() {
+        let mut sweeps = 0;
+        let scan = scan_until_stable(3, |n| {
+            sweeps += 1;
+            sweep_of(&[n as u8])
+        });
+
+        assert_eq!(sweeps, 3);
+        assert_eq!(scan.alive, vec![Ipv4Addr::new(10, 0, 0, 3)]);
+    }
+
+    #[test]
+    fn scan_until_stable_with_one_sweep_runs_once() {
+        let mut sweeps = 0;
+        scan_until_stable(1, |_| {
+            sweeps += 1;
+            sweep_of(&[1])
+        });
+
+        assert_eq!(sweeps, 1);
+    }
+
+    #[test]
+    fn subnet_parse_normalizes_leading_zeros() {
+        let subnet = Subnet::parse("10.0.01").unwrap();
+
+        assert_eq!(subnet.to_string(), "10.0.1");
+        assert_eq!(subnet.host(5), Ipv4Addr::new(10, 0, 1, 5));
+        assert!(Subnet::parse("10.0").is_none());
+        assert!(Subnet::parse("10.0.256").is_none());
+    }
+
+    #[test]
+    fn check_subnet_flags_link_local_and_multicast_unless_allowed() {
+        assert!(check_subnet("169.254.1", false).is_err());
+        assert!(check_subnet("239.1.1", false).is_err());
+        assert!(check_subnet("169.254.1", true).is_ok());
+        assert!(check_subnet("239.1.1", true).is_ok());
+
+        assert!(check_subnet("223.1.1", false).is_ok());
+        assert!(check_subnet("240.1.1", false).is_ok());
+    }
+
+    #[test]
+    fn check_subnets_drops_repeats_in_first_seen_order() {
+        let list: Vec<String> = ["10.0.1", "10.0.0", "10.0.01", "10.0.0"].iter().map(|s| s.to_string()).collect();
+        let subnets: Vec<String> = check_subnets(&list, false).unwrap().iter().map(Subnet::to_string).collect();
+
+        assert_eq!(subnets, vec!["10.0.1", "10.0.0"]);
+    }
+
+    #[test]
+    fn parse_ttl_reads_unix_and_windows_output() {
+        assert_eq!(parse_ttl("64 bytes from 10.0.0.1: icmp_seq=1 ttl=64 time=0.42 ms"), Some(64));
+        assert_eq!(parse_ttl("Reply from 10.0.0.1: bytes=32 time<1ms TTL=128"), Some(128));
+        assert_eq!(parse_ttl("Request timed out."), None);
+    }
+
+    #[test]
+    fn os_family_boundaries_follow_initial_ttls() {
+        let family = |ttl| OsFamily::from_ttl(ttl).to_string();
+
+        assert_eq!(family(64), "Unix-like");
+        assert_eq!(family(65), "Windows");
+        assert_eq!(family(128), "Windows");
+        assert_eq!(family(129), "network device");
+    }
+
+    #[test]
+    fn parse_rtt_reads_unix_and_windows_output() {
+        let unix = "64 bytes from 10.0.0.1: icmp_seq=1 ttl=64 time=0.42 ms\n1 packets transmitted, 1 received, 0% packet loss, time 0ms";
+        let windows = "Reply from 10.0.0.1: bytes=32 time<1ms TTL=128";
+
+        assert_eq!(parse_rtt(unix), Some(Duration::from_micros(420)));
+        assert_eq!(parse_rtt(windows), Some(Duration::from_millis(1)));
+        assert_eq!(parse_rtt("Request timed out."), None);
+    }
+
+    #[test]
+    fn expand_template_substitutes_every_ip_placeholder() {
+        let ip = Ipv4Addr::new(10, 0, 0, 5);
+
+        assert_eq!(expand_template("ssh {ip} uptime", ip), "ssh 10.0.0.5 uptime");
+        assert_eq!(expand_template("ping -c1 {ip} && echo {ip} ok", ip), "ping -c1 10.0.0.5 && echo 10.0.0.5 ok");
+        assert_eq!(expand_template("uptime", ip), "uptime");
+    }
+
+    #[test]
+    fn host_order_puts_gateways_first_and_keeps_every_host() {
+        let default = host_order(false);
+        assert_eq!(default, (1..=254).collect::<Vec<u8>>());
+
+        let gateways_first = host_order(true);
+        assert_eq!(&gateways_first[..2], &[1, 254]);
+        assert_eq!(gateways_first.iter().cloned().collect::<BTreeSet<u8>>().len(), 254);
+        assert_eq!(gateways_first.len(), 254);
+    }
+
+    #[test]
+    fn parse_hop_reads_traceroute_and_tracert_lines() {
+        let unix = parse_hop(" 3  10.0.0.1  4.211 ms").unwrap();
+        assert_eq!((unix.ttl, unix.addr, unix.rtt), (3, Some(Ipv4Addr::new(10, 0, 0, 1)), Some(Duration::from_micros(4211))));
+
+        let windows = parse_hop("  1    <1 ms    <1 ms    <1 ms  192.168.1.1").unwrap();
+        assert_eq!((windows.ttl, windows.addr, windows.rtt), (1, Some(Ipv4Addr::new(192, 168, 1, 1)), Some(Duration::from_millis(1))));
+
+        let unix_silent = parse_hop(" 2  *").unwrap();
+        assert_eq!((unix_silent.ttl, unix_silent.addr, unix_silent.rtt), (2, None, None));
+
+        let windows_silent = parse_hop("  2     *        *        *     Request timed out.").unwrap();
+        assert_eq!((windows_silent.ttl, windows_silent.addr, windows_silent.rtt), (2, None, None));
+
+        assert!(parse_hop("traceroute to 10.0.0.9 (10.0.0.9), 30 hops max, 60 byte packets").is_none());
+        assert!(parse_hop("Tracing route to 10.0.0.9 over a maximum of 30 hops").is_none());
+        assert!(parse_hop("Trace complete.").is_none());
+        assert!(parse_hop("").is_none());
+    }
+
+    #[test]
+    fn fastest_hosts_ranks_by_rtt() {
+        let mut rtts = BTreeMap::new();
+        rtts.insert(Ipv4Addr::new(10, 0, 0, 1), Duration::from_millis(9));
+        rtts.insert(Ipv4Addr::new(10, 0, 0, 2), Duration::from_millis(1));
+        rtts.insert(Ipv4Addr::new(10, 0, 0, 3), Duration::from_millis(4));
+
+        let fastest: Vec<Ipv4Addr> = fastest_hosts(&rtts, 2).into_iter().map(|(ip, _)| ip).collect();
+        assert_eq!(fastest, vec![Ipv4Addr::new(10, 0, 0, 2), Ipv4Addr::new(10, 0, 0, 3)]);
+    }
+
+    #[test]
+    fn group_by_subnet_keeps_every_host() {
+        let results = [Ipv4Addr::new(10, 0, 1, 5), Ipv4Addr::new(10, 0, 0, 9), Ipv4Addr::new(10, 0, 1, 2)];
+        let groups = group_by_subnet(&results);
+
+        assert_eq!(groups[&Ipv4Addr::new(10, 0, 0, 0)], vec![Ipv4Addr::new(10, 0, 0, 9)]);
+        assert_eq!(groups[&Ipv4Addr::new(10, 0, 1, 0)], vec![Ipv4Addr::new(10, 0, 1, 2), Ipv4Addr::new(10, 0, 1, 5)]);
+    }
+
+    #[test]
+    fn alive_fingerprint_hashes_every_host_in_any_order() {
+        let empty = alive_fingerprint(&[]);
+        let forward = alive_fingerprint(&[Ipv4Addr::new(10, 0, 1, 1), Ipv4Addr::new(10, 0, 1, 7)]);
+        let reversed = alive_fingerprint(&[Ipv4Addr::new(10, 0, 1, 7), Ipv4Addr::new(10, 0, 1, 1)]);
+        let fewer = alive_fingerprint(&[Ipv4Addr::new(10, 0, 1, 1)]);
+
+        assert_ne!(forward, empty);
+        assert_eq!(forward, reversed);
+        assert_ne!(forward, fewer);
+    }
+
+    #[test]
+    fn strict_mode_surfaces_panicked_probe() {
+        let probes = vec![probe("10.0.0.1", false), probe("10.0.0.2", true)];
+        let result = join_probes(probes, true, &new_progress(), 2, ProgressCadence::EveryHosts(1));
+
+        let message = result.unwrap_err();
+        assert!(message.contains("10.0.0.2"), "unexpected error: {}", message);
+    }
+
+    #[test]
+    fn lenient_mode_records_panicked_probe() {
+        let probes = vec![probe("10.0.0.1", false), probe("10.0.0.2", true)];
+        let result = join_probes(probes, false, &new_progress(), 2, ProgressCadence::EveryHosts(1));
+
+        assert_eq!(result.unwrap(), vec![Ipv4Addr::new(10, 0, 0, 2)]);
+    }
+
+    #[test]
+    fn panicked_probe_still_counts_towards_progress() {
+        let progress = Arc::new(new_progress());
+        let probes = vec![
+            counting_probe("10.0.0.1", &progress, false, false),
+            counting_probe("10.0.0.2", &progress, true, false),
+            counting_probe("10.0.0.3", &progress, false, false),
+        ];
+        join_probes(probes, false, &progress, 3, ProgressCadence::EveryHosts(1)).unwrap();
+
+        assert_eq!(progress.lock().unwrap_or_else(PoisonError::into_inner).completed, 3);
+    }
+
+    #[test]
+    fn probe_panicking_after_counting_is_not_counted_twice() {
+        let progress = Arc::new(new_progress());
+        let probes = vec![
+            counting_probe("10.0.0.1", &progress, false, true),
+            counting_probe("10.0.0.2", &progress, false, false),
+            counting_probe("10.0.0.3", &progress, false, false),
+        ];
+        join_probes(probes, false, &progress, 3, ProgressCadence::EveryHosts(1)).unwrap();
+
+        assert_eq!(progress.lock().unwrap_or_else(PoisonError::into_inner).completed, 3);
+    }
+}